@@ -0,0 +1,138 @@
+//! A background-thread output that keeps slow destinations off the caller's thread.
+//!
+//! See [`async_output`] for the entry point.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use log;
+
+use FernLog;
+
+/// A destination that an [`AsyncOutput`]'s writer thread can drain rendered lines into.
+///
+/// Unlike [`FernLog`], this only ever sees the already-formatted message body and the level of
+/// the record that produced it, never the full `log::LogRecord` - by the time a line reaches the
+/// writer thread, the record itself has gone out of scope back on the logging thread (its
+/// `fmt::Arguments` borrow can't outlive the original logging call). The level is cheap to carry
+/// across the channel alongside the line, and is enough for the destinations that need to
+/// know it: plain files ignore it, while `Syslog` (see its `AsyncSink` impl) uses it to pick the
+/// PRI severity for the frame it emits.
+pub trait AsyncSink: Send + 'static {
+    /// Writes one already-formatted line (without a trailing newline) to the destination, for a
+    /// record that was originally logged at `level`.
+    fn write_line(&mut self, level: log::LogLevel, line: &str) -> io::Result<()>;
+}
+
+impl AsyncSink for File {
+    fn write_line(&mut self, _level: log::LogLevel, line: &str) -> io::Result<()> {
+        writeln!(self, "{}", line)
+    }
+}
+
+/// What an [`AsyncOutput`] does when its bounded queue is full and a new record arrives.
+#[derive(Copy, Clone, Debug)]
+pub enum OverflowPolicy {
+    /// Block the calling thread until the writer thread catches up.
+    Block,
+    /// Drop the new record immediately, incrementing the dropped-message counter.
+    DropNewest,
+}
+
+enum Message {
+    Line(log::LogLevel, String),
+    Shutdown,
+}
+
+/// An `Output` which formats records on the caller's thread but defers the actual write to a
+/// dedicated background thread, so a slow destination never stalls the logging call site.
+///
+/// Construct with [`async_output`]. Dropping this flushes and joins the writer thread.
+pub struct AsyncOutput {
+    sender: Mutex<Option<SyncSender<Message>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    policy: OverflowPolicy,
+    dropped: Arc<AtomicUsize>,
+}
+
+/// Wraps `sink` in a background writer thread, returning an `Output` that hands formatted lines
+/// off over a channel of `capacity` pending messages instead of writing inline.
+///
+/// ```no_run
+/// # use fern::async_output::OverflowPolicy;
+/// let file = fern::log_file("output.log").expect("failed to open log file");
+/// fern::async_output(file, 1024, OverflowPolicy::DropNewest);
+/// ```
+pub fn async_output<S: AsyncSink>(sink: S, capacity: usize, policy: OverflowPolicy) -> AsyncOutput {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    let dropped = Arc::new(AtomicUsize::new(0));
+
+    let mut sink = sink;
+    let handle = thread::spawn(move || {
+        for message in receiver.iter() {
+            match message {
+                Message::Line(level, line) => {
+                    let _ = sink.write_line(level, &line);
+                }
+                Message::Shutdown => break,
+            }
+        }
+    });
+
+    AsyncOutput {
+        sender: Mutex::new(Some(sender)),
+        handle: Mutex::new(Some(handle)),
+        policy: policy,
+        dropped: dropped,
+    }
+}
+
+impl AsyncOutput {
+    /// The number of records dropped so far because the queue was full and the policy is
+    /// [`OverflowPolicy::DropNewest`].
+    pub fn dropped_count(&self) -> usize {
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    fn enqueue(&self, level: log::LogLevel, line: String) {
+        let sender_guard = self.sender.lock().unwrap();
+        let sender = match *sender_guard {
+            Some(ref sender) => sender,
+            None => return,
+        };
+
+        let message = Message::Line(level, line);
+        match self.policy {
+            OverflowPolicy::Block => {
+                let _ = sender.send(message);
+            }
+            OverflowPolicy::DropNewest => {
+                if let Err(mpsc::TrySendError::Full(_)) = sender.try_send(message) {
+                    self.dropped.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+    }
+}
+
+impl FernLog for AsyncOutput {
+    fn log_args(&self, payload: &fmt::Arguments, record: &log::LogRecord) {
+        self.enqueue(record.level(), format!("{}", payload));
+    }
+}
+
+impl Drop for AsyncOutput {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.lock().unwrap().take() {
+            let _ = sender.send(Message::Shutdown);
+        }
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
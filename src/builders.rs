@@ -0,0 +1,295 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::sync::Mutex;
+
+use log;
+
+use async_output::AsyncOutput;
+use colors::ColorOutput;
+use errors::InitError;
+use rotation::RotatingFileOutput;
+#[cfg(unix)]
+use syslog::Syslog;
+use {FernLog, Filter, Formatter};
+
+/// The base dispatch logger, responsible for gathering together a set of output targets, a
+/// level filter, and an optional message format, and forwarding log records which pass the
+/// filter to every child.
+///
+/// This struct implements a builder pattern; see the crate-level docs for usage examples.
+pub struct Dispatch {
+    pub(crate) format: Option<Box<Formatter>>,
+    pub(crate) children: Vec<Output>,
+    pub(crate) level: log::LogLevelFilter,
+    pub(crate) filters: Vec<Box<Filter>>,
+    pub(crate) module_levels: Vec<(String, log::LogLevelFilter)>,
+}
+
+impl Dispatch {
+    /// Creates a new empty `Dispatch` with no formatting, an unlimited level filter, and no
+    /// children.
+    pub fn new() -> Dispatch {
+        Dispatch {
+            format: None,
+            children: Vec::new(),
+            level: log::LogLevelFilter::Trace,
+            filters: Vec::new(),
+            module_levels: Vec::new(),
+        }
+    }
+
+    /// Sets the formatter of this dispatch, a closure which takes in a message and record and
+    /// writes the final output.
+    pub fn format<F>(mut self, formatter: F) -> Self
+        where F: Fn(&mut fmt::Write, &fmt::Arguments, &log::LogRecord) -> fmt::Result + Sync + Send + 'static
+    {
+        self.format = Some(Box::new(formatter));
+        self
+    }
+
+    /// Sets the overarching level filter for this dispatch. Any record with a level less severe
+    /// than this will be ignored.
+    pub fn level(mut self, level: log::LogLevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Adds a custom filter which records must pass in order to be sent onward.
+    pub fn filter<F>(mut self, filter: F) -> Self
+        where F: Fn(&log::LogMetadata) -> bool + Send + Sync + 'static
+    {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Overrides the level filter for a specific module (and its submodules), regardless of the
+    /// blanket `level()`.
+    ///
+    /// When a record's target matches more than one override, the longest (most specific) match
+    /// wins; unmatched targets fall back to `level()`.
+    pub fn level_for<T: Into<String>>(mut self, module: T, level: log::LogLevelFilter) -> Self {
+        self.module_levels.push((module.into(), level));
+        self
+    }
+
+    /// Adds a child to this dispatch, to which all records passing this dispatch's filters will
+    /// be forwarded.
+    pub fn chain<T: Into<Output>>(mut self, logger: T) -> Self {
+        self.children.push(logger.into());
+        self
+    }
+
+    /// Adds a child like `chain`, but only forwards records whose level passes `level`, e.g.
+    /// `chain_at_level(log::LogLevelFilter::Error, error_log)` to mirror error records into a
+    /// dedicated alert sink alongside the rest of this dispatch's destinations.
+    ///
+    /// Equivalent to nesting a `Dispatch::new().level(level).chain(logger)` as a child, but
+    /// without the extra indirection.
+    pub fn chain_at_level<T: Into<Output>>(mut self, level: log::LogLevelFilter, logger: T) -> Self {
+        let gated = LevelGate {
+            level: level,
+            inner: logger.into().into_fern_log(),
+        };
+        self.children.push(Output::Other(Box::new(gated)));
+        self
+    }
+
+    /// Builds this dispatch into a boxed `FernLog` implementation, without installing it as the
+    /// global logger. This is useful for nesting dispatches inside other dispatches via
+    /// `chain()`.
+    pub fn into_shared(self) -> Box<FernLog> {
+        self.into_log()
+    }
+
+    /// Builds this `Dispatch` and installs it as the global logger for the `log` crate.
+    ///
+    /// This will fail if and only if another fern or `log` logger has already been set as the
+    /// global logger.
+    ///
+    /// The returned `Reloadable` handle can later swap the installed configuration out for a
+    /// freshly built `Dispatch`, without needing to call `log::set_logger` again.
+    pub fn set_global(self) -> Result<::reload::Reloadable, InitError> {
+        let (proxy, handle) = ::reload::Reloadable::install(self);
+        log::set_logger(|max_level| {
+                max_level.set(log::LogLevelFilter::Trace);
+                proxy
+            })?;
+        Ok(handle)
+    }
+}
+
+/// A single destination for log records: stdout, stderr, a file, a nested `Dispatch`, or one of
+/// the other outputs listed below.
+pub enum Output {
+    /// Logs to stdout, with the given newline-inserting wrapper.
+    Stdout(StdoutWriter),
+    /// Logs to stderr, with the given newline-inserting wrapper.
+    Stderr(StderrWriter),
+    /// Logs to a file, with the given mutex-guarded handle.
+    File(Mutex<File>),
+    /// Forwards to another `Dispatch`, allowing arbitrarily nested configuration trees.
+    Dispatch(Dispatch),
+    /// Logs to a size- or age-rotating file, as returned by [`::rotating_log_file`].
+    RotatingFile(RotatingFileOutput),
+    /// Logs through a background writer thread, as returned by [`::async_output`].
+    Async(AsyncOutput),
+    /// Logs to a tty-aware, color-stripping stdout/stderr, as returned by
+    /// `colors::stdout`/`colors::stderr`.
+    Color(ColorOutput),
+    /// Logs to syslog, as returned by `Syslog::unix`/`Syslog::udp`/`Syslog::tcp`.
+    #[cfg(unix)]
+    Syslog(Syslog),
+    /// Any other destination which implements `FernLog`, wrapped for convenience.
+    Other(Box<FernLog>),
+}
+
+/// A wrapper around stdout which appends a trailing newline to every record.
+pub struct StdoutWriter(Mutex<io::Stdout>);
+
+/// A wrapper around stderr which appends a trailing newline to every record.
+pub struct StderrWriter(Mutex<io::Stderr>);
+
+impl FernLog for StdoutWriter {
+    fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+        use std::io::Write;
+        let _ = writeln!(self.0.lock().unwrap(), "{}", payload);
+    }
+}
+
+impl FernLog for StderrWriter {
+    fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+        use std::io::Write;
+        let _ = writeln!(self.0.lock().unwrap(), "{}", payload);
+    }
+}
+
+impl FernLog for Mutex<File> {
+    fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+        use std::io::Write;
+        let _ = writeln!(self.lock().unwrap(), "{}", payload);
+    }
+}
+
+/// A `FernLog` wrapper which only forwards records whose level passes a minimum-severity gate,
+/// used by `Dispatch::chain_at_level`.
+struct LevelGate {
+    level: log::LogLevelFilter,
+    inner: Box<FernLog>,
+}
+
+impl FernLog for LevelGate {
+    fn log_args(&self, payload: &fmt::Arguments, record: &log::LogRecord) {
+        if record.level() <= self.level {
+            self.inner.log_args(payload, record);
+        }
+    }
+}
+
+impl Output {
+    pub(crate) fn into_fern_log(self) -> Box<FernLog> {
+        match self {
+            Output::Stdout(writer) => Box::new(writer),
+            Output::Stderr(writer) => Box::new(writer),
+            Output::File(file) => Box::new(file),
+            Output::Dispatch(dispatch) => dispatch.into_log(),
+            Output::RotatingFile(rotating) => Box::new(rotating),
+            Output::Async(async_output) => Box::new(async_output),
+            Output::Color(color) => Box::new(color),
+            #[cfg(unix)]
+            Output::Syslog(syslog) => Box::new(syslog),
+            Output::Other(other) => other,
+        }
+    }
+}
+
+impl From<io::Stdout> for Output {
+    fn from(stream: io::Stdout) -> Output {
+        Output::Stdout(StdoutWriter(Mutex::new(stream)))
+    }
+}
+
+impl From<io::Stderr> for Output {
+    fn from(stream: io::Stderr) -> Output {
+        Output::Stderr(StderrWriter(Mutex::new(stream)))
+    }
+}
+
+impl From<File> for Output {
+    fn from(file: File) -> Output {
+        Output::File(Mutex::new(file))
+    }
+}
+
+impl From<Dispatch> for Output {
+    fn from(dispatch: Dispatch) -> Output {
+        Output::Dispatch(dispatch)
+    }
+}
+
+impl From<Box<FernLog>> for Output {
+    fn from(custom: Box<FernLog>) -> Output {
+        Output::Other(custom)
+    }
+}
+
+impl From<RotatingFileOutput> for Output {
+    fn from(rotating: RotatingFileOutput) -> Output {
+        Output::RotatingFile(rotating)
+    }
+}
+
+impl From<AsyncOutput> for Output {
+    fn from(async_output: AsyncOutput) -> Output {
+        Output::Async(async_output)
+    }
+}
+
+impl From<ColorOutput> for Output {
+    fn from(color: ColorOutput) -> Output {
+        Output::Color(color)
+    }
+}
+
+#[cfg(unix)]
+impl From<Syslog> for Output {
+    fn from(syslog: Syslog) -> Output {
+        Output::Syslog(syslog)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct RecordingSink(Arc<Mutex<Vec<String>>>);
+
+    impl FernLog for RecordingSink {
+        fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+            self.0.lock().unwrap().push(payload.to_string());
+        }
+    }
+
+    #[test]
+    fn level_gate_only_forwards_records_at_or_above_its_level() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let gate = Arc::new(LevelGate {
+            level: log::LogLevelFilter::Warn,
+            inner: Box::new(RecordingSink(seen.clone())),
+        });
+
+        for &(level, message) in &[(log::LogLevel::Error, "error message"),
+                                    (log::LogLevel::Warn, "warn message"),
+                                    (log::LogLevel::Info, "info message"),
+                                    (log::LogLevel::Debug, "debug message")] {
+            let gate = gate.clone();
+            ::test_support::with_record(level, "test", message, move |record| {
+                gate.log_args(record.args(), record);
+            });
+        }
+
+        assert_eq!(*seen.lock().unwrap(),
+                   vec!["error message".to_string(), "warn message".to_string()]);
+    }
+}
@@ -0,0 +1,295 @@
+//! ANSI-colored level formatting, with automatic plain-text fallback when output isn't an
+//! interactive terminal.
+//!
+//! See [`ColoredLevelConfig`] for coloring levels inside a `Dispatch::format` closure, and
+//! [`stdout`]/[`stderr`] for chaining a destination that strips the color codes back out
+//! whenever the underlying stream isn't a tty (e.g. redirected to a file or pipe).
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use log;
+
+use FernLog;
+
+/// An ANSI terminal color, as understood by [`ColoredLevelConfig`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// `30`
+    Black,
+    /// `31`
+    Red,
+    /// `32`
+    Green,
+    /// `33`
+    Yellow,
+    /// `34`
+    Blue,
+    /// `35`
+    Magenta,
+    /// `36`
+    Cyan,
+    /// `37`
+    White,
+    /// `90`
+    BrightBlack,
+    /// `91`
+    BrightRed,
+    /// `92`
+    BrightGreen,
+    /// `93`
+    BrightYellow,
+    /// `94`
+    BrightBlue,
+    /// `95`
+    BrightMagenta,
+    /// `96`
+    BrightCyan,
+    /// `97`
+    BrightWhite,
+}
+
+impl Color {
+    fn code(&self) -> u8 {
+        match *self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::BrightBlack => 90,
+            Color::BrightRed => 91,
+            Color::BrightGreen => 92,
+            Color::BrightYellow => 93,
+            Color::BrightBlue => 94,
+            Color::BrightMagenta => 95,
+            Color::BrightCyan => 96,
+            Color::BrightWhite => 97,
+        }
+    }
+}
+
+/// Which color each `log::LogLevel` is painted by [`ColoredLevelConfig::color`].
+///
+/// Defaults to the conventional red/yellow/green/blue/bright-black scheme for
+/// error/warn/info/debug/trace.
+///
+/// ```
+/// # use fern::colors::ColoredLevelConfig;
+/// fern::Dispatch::new()
+///     .format(|out, message, record| {
+///         use std::fmt::Write;
+///         let colors = ColoredLevelConfig::new();
+///         write!(out, "[{}] {}", colors.color(record.level()), message)
+///     })
+///     # ;
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ColoredLevelConfig {
+    error: Color,
+    warn: Color,
+    info: Color,
+    debug: Color,
+    trace: Color,
+}
+
+impl ColoredLevelConfig {
+    /// Creates a `ColoredLevelConfig` with the default color scheme.
+    pub fn new() -> Self {
+        ColoredLevelConfig {
+            error: Color::Red,
+            warn: Color::Yellow,
+            info: Color::Green,
+            debug: Color::Blue,
+            trace: Color::BrightBlack,
+        }
+    }
+
+    /// Sets the color used for `LogLevel::Error`.
+    pub fn error(mut self, color: Color) -> Self {
+        self.error = color;
+        self
+    }
+
+    /// Sets the color used for `LogLevel::Warn`.
+    pub fn warn(mut self, color: Color) -> Self {
+        self.warn = color;
+        self
+    }
+
+    /// Sets the color used for `LogLevel::Info`.
+    pub fn info(mut self, color: Color) -> Self {
+        self.info = color;
+        self
+    }
+
+    /// Sets the color used for `LogLevel::Debug`.
+    pub fn debug(mut self, color: Color) -> Self {
+        self.debug = color;
+        self
+    }
+
+    /// Sets the color used for `LogLevel::Trace`.
+    pub fn trace(mut self, color: Color) -> Self {
+        self.trace = color;
+        self
+    }
+
+    /// Wraps `level` in the ANSI escape codes for its configured color.
+    ///
+    /// The result implements `Display`, so it can be interpolated directly into a
+    /// `Dispatch::format` closure. The escape codes are emitted unconditionally here; pairing
+    /// this with [`stdout`]/[`stderr`] as the chained destination strips them back out when the
+    /// destination isn't a terminal.
+    pub fn color(&self, level: log::LogLevel) -> ColoredLevel {
+        let color = match level {
+            log::LogLevel::Error => self.error,
+            log::LogLevel::Warn => self.warn,
+            log::LogLevel::Info => self.info,
+            log::LogLevel::Debug => self.debug,
+            log::LogLevel::Trace => self.trace,
+        };
+        ColoredLevel { level: level, color: color }
+    }
+}
+
+impl Default for ColoredLevelConfig {
+    fn default() -> Self {
+        ColoredLevelConfig::new()
+    }
+}
+
+/// A `log::LogLevel` wrapped with ANSI color codes, returned by [`ColoredLevelConfig::color`].
+pub struct ColoredLevel {
+    level: log::LogLevel,
+    color: Color,
+}
+
+impl fmt::Display for ColoredLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\x1b[{}m{}\x1b[0m", self.color.code(), self.level)
+    }
+}
+
+enum Stream {
+    Stdout(Mutex<io::Stdout>),
+    Stderr(Mutex<io::Stderr>),
+}
+
+/// A stdout/stderr destination that strips ANSI color escapes from every record unless the
+/// underlying stream is an interactive terminal, determined once at construction time.
+///
+/// Construct with [`stdout`] or [`stderr`].
+pub struct ColorOutput {
+    stream: Stream,
+    strip_colors: bool,
+}
+
+impl FernLog for ColorOutput {
+    fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+        let line = payload.to_string();
+        let line = if self.strip_colors { strip_ansi(&line) } else { line };
+
+        let result = match self.stream {
+            Stream::Stdout(ref stream) => writeln!(stream.lock().unwrap(), "{}", line),
+            Stream::Stderr(ref stream) => writeln!(stream.lock().unwrap(), "{}", line),
+        };
+        let _ = result;
+    }
+}
+
+/// Chains stdout, stripping `\x1b[...m`-style color escapes from every record unless stdout is
+/// an interactive terminal.
+pub fn stdout() -> ColorOutput {
+    ColorOutput {
+        strip_colors: !is_tty(RawFd::Stdout),
+        stream: Stream::Stdout(Mutex::new(io::stdout())),
+    }
+}
+
+/// Chains stderr, stripping `\x1b[...m`-style color escapes from every record unless stderr is
+/// an interactive terminal.
+pub fn stderr() -> ColorOutput {
+    ColorOutput {
+        strip_colors: !is_tty(RawFd::Stderr),
+        stream: Stream::Stderr(Mutex::new(io::stderr())),
+    }
+}
+
+/// Removes `\x1b[...m`-style ANSI escape sequences from `input`, leaving text without `\x1b`
+/// untouched. Any `\x1b` is dropped together with the character after it; when that character
+/// is `[`, everything up to and including the next `m` (or to the end of `input`) is dropped.
+fn strip_ansi(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        if chars.next() != Some('[') {
+            continue;
+        }
+        for c in &mut chars {
+            if c == 'm' {
+                break;
+            }
+        }
+    }
+
+    output
+}
+
+enum RawFd {
+    Stdout,
+    Stderr,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_ansi_removes_color_codes() {
+        let colored = format!("{}", ColoredLevelConfig::new().color(log::LogLevel::Error));
+        assert_eq!(strip_ansi(&colored), "ERROR");
+    }
+
+    #[test]
+    fn strip_ansi_leaves_plain_text_untouched() {
+        assert_eq!(strip_ansi("plain message, no escapes here"), "plain message, no escapes here");
+    }
+
+    #[test]
+    fn strip_ansi_handles_multiple_sequences() {
+        let input = "\x1b[31mred\x1b[0m and \x1b[32mgreen\x1b[0m";
+        assert_eq!(strip_ansi(input), "red and green");
+    }
+}
+
+#[cfg(unix)]
+fn is_tty(fd: RawFd) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+    }
+
+    let raw = match fd {
+        RawFd::Stdout => io::stdout().as_raw_fd(),
+        RawFd::Stderr => io::stderr().as_raw_fd(),
+    };
+    unsafe { isatty(raw) != 0 }
+}
+
+#[cfg(not(unix))]
+fn is_tty(_fd: RawFd) -> bool {
+    // No dependency-free way to query console-ness on non-Unix targets; fall back to the safe
+    // choice of always stripping color codes.
+    false
+}
@@ -0,0 +1,244 @@
+//! A declarative, serde-serializable description of a logging setup, for loading `Dispatch`
+//! trees from TOML/JSON/YAML instead of hand-writing builder chains.
+//!
+//! See [`LoggingConfig`] for the entry point.
+
+use std::fmt;
+use std::time::SystemTime;
+
+use log;
+
+use builders::Dispatch;
+use datetime;
+use errors::InitError;
+#[cfg(unix)]
+use syslog::{Facility, Framing, Syslog};
+
+/// A serializable mirror of `log::LogLevelFilter`, since the `log` crate's own type doesn't
+/// implement `Serialize`/`Deserialize`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelFilter {
+    /// See `log::LogLevelFilter::Off`.
+    Off,
+    /// See `log::LogLevelFilter::Error`.
+    Error,
+    /// See `log::LogLevelFilter::Warn`.
+    Warn,
+    /// See `log::LogLevelFilter::Info`.
+    Info,
+    /// See `log::LogLevelFilter::Debug`.
+    Debug,
+    /// See `log::LogLevelFilter::Trace`.
+    Trace,
+}
+
+impl Default for LevelFilter {
+    /// Matches `Dispatch::new()`'s default of letting everything through.
+    fn default() -> Self {
+        LevelFilter::Trace
+    }
+}
+
+impl LevelFilter {
+    fn into_log(self) -> log::LogLevelFilter {
+        match self {
+            LevelFilter::Off => log::LogLevelFilter::Off,
+            LevelFilter::Error => log::LogLevelFilter::Error,
+            LevelFilter::Warn => log::LogLevelFilter::Warn,
+            LevelFilter::Info => log::LogLevelFilter::Info,
+            LevelFilter::Debug => log::LogLevelFilter::Debug,
+            LevelFilter::Trace => log::LogLevelFilter::Trace,
+        }
+    }
+}
+
+/// One destination for a [`LoggingConfig`]'s records, equivalent to one `Dispatch::chain` call.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Destination {
+    /// Logs to stdout.
+    Stdout,
+    /// Logs to stderr.
+    Stderr,
+    /// Appends to the file at `path`, creating it if necessary.
+    File {
+        /// Path of the file to append to.
+        path: String,
+    },
+    /// Sends to a syslog daemon over a Unix datagram socket.
+    #[cfg(unix)]
+    Syslog {
+        /// Path of the Unix datagram socket, typically `/dev/log`.
+        path: String,
+        /// Which syslog facility to tag records with.
+        facility: Facility,
+        /// Which syslog framing to use. Defaults to `Framing::Rfc3164`.
+        #[serde(default)]
+        framing: Option<Framing>,
+    },
+}
+
+impl Destination {
+    fn into_output(self) -> Result<::Output, InitError> {
+        match self {
+            Destination::Stdout => Ok(::std::io::stdout().into()),
+            Destination::Stderr => Ok(::std::io::stderr().into()),
+            Destination::File { path } => Ok(::log_file(path)?.into()),
+            #[cfg(unix)]
+            Destination::Syslog { path, facility, framing } => {
+                let mut syslog = Syslog::unix(path, facility)?;
+                if let Some(framing) = framing {
+                    syslog = syslog.framing(framing);
+                }
+                Ok(syslog.into())
+            }
+        }
+    }
+}
+
+/// A declarative description of one logger: a blanket level, per-module overrides, a format
+/// template, and a list of destinations.
+///
+/// Deserialize this from a config file with serde, then call [`LoggingConfig::into_dispatch`] to
+/// get the equivalent `Dispatch` tree.
+///
+/// ```no_run
+/// # use fern::config::{LoggingConfig, Destination};
+/// let config = LoggingConfig {
+///     level: Default::default(),
+///     module_levels: Vec::new(),
+///     format: Some("[{level}][{target}] {message}".to_string()),
+///     destinations: vec![Destination::Stdout],
+/// };
+///
+/// config.into_dispatch()
+///     .expect("invalid logging config")
+///     .set_global()
+///     .expect("global logger already initialized");
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// The blanket level filter, equivalent to `Dispatch::level`.
+    #[serde(default)]
+    pub level: LevelFilter,
+    /// Per-module level overrides, equivalent to repeated `Dispatch::level_for` calls.
+    #[serde(default)]
+    pub module_levels: Vec<(String, LevelFilter)>,
+    /// An optional [`format_template`] string. Leaving this unset keeps `Dispatch`'s default of
+    /// passing messages through unformatted.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// The destinations records are forwarded to, equivalent to repeated `Dispatch::chain` calls.
+    pub destinations: Vec<Destination>,
+}
+
+impl LoggingConfig {
+    /// Builds the `Dispatch` tree described by this config.
+    ///
+    /// Fails if a `File` destination can't be opened, or a `Syslog` destination can't connect.
+    pub fn into_dispatch(self) -> Result<Dispatch, InitError> {
+        let mut dispatch = Dispatch::new().level(self.level.into_log());
+
+        for (module, level) in self.module_levels {
+            dispatch = dispatch.level_for(module, level.into_log());
+        }
+
+        if let Some(template) = self.format {
+            dispatch = dispatch.format(move |out, message, record| {
+                write!(out, "{}", format_template(&template, message, record))
+            });
+        }
+
+        for destination in self.destinations {
+            dispatch = dispatch.chain(destination.into_output()?);
+        }
+
+        Ok(dispatch)
+    }
+}
+
+/// Resolves a format template against a log record, for use as (or inside) a `Dispatch::format`
+/// closure.
+///
+/// Recognized tokens are `{level}`, `{target}`, `{file}`, `{line}`, `{message}`, and
+/// `{datetime:FORMAT}`, where `FORMAT` is the `strftime`-like pattern accepted by
+/// [`::datetime::strftime`]. Any other `{token}` is left in the output verbatim.
+pub fn format_template(template: &str, message: &fmt::Arguments, record: &log::LogRecord) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => {
+                output.push('{');
+                output.push_str(rest);
+                rest = "";
+                break;
+            }
+        };
+
+        let token = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if token == "level" {
+            output.push_str(&record.level().to_string());
+        } else if token == "target" {
+            output.push_str(record.target());
+        } else if token == "file" {
+            output.push_str(record.location().file());
+        } else if token == "line" {
+            output.push_str(&record.location().line().to_string());
+        } else if token == "message" {
+            output.push_str(&message.to_string());
+        } else if token.starts_with("datetime:") {
+            let pattern = &token["datetime:".len()..];
+            output.push_str(&datetime::strftime(SystemTime::now(), pattern));
+        } else {
+            output.push('{');
+            output.push_str(token);
+            output.push('}');
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn format_template_resolves_level_target_and_message() {
+        let rendered = Arc::new(Mutex::new(String::new()));
+        let rendered_in_callback = rendered.clone();
+
+        ::test_support::with_record(log::LogLevel::Error, "my::target", "hello world", move |record| {
+            let message = format_args!("hello world");
+            let output = format_template("[{level}][{target}] {message}", &message, record);
+            *rendered_in_callback.lock().unwrap() = output;
+        });
+
+        assert_eq!(*rendered.lock().unwrap(), "[ERROR][my::target] hello world");
+    }
+
+    #[test]
+    fn format_template_leaves_unknown_tokens_verbatim() {
+        let rendered = Arc::new(Mutex::new(String::new()));
+        let rendered_in_callback = rendered.clone();
+
+        ::test_support::with_record(log::LogLevel::Info, "my::target", "hi", move |record| {
+            let message = format_args!("hi");
+            let output = format_template("{nope}{message}", &message, record);
+            *rendered_in_callback.lock().unwrap() = output;
+        });
+
+        assert_eq!(*rendered.lock().unwrap(), "{nope}hi");
+    }
+}
@@ -0,0 +1,80 @@
+//! Minimal dependency-free wall-clock formatting, shared by the outputs that need a timestamp
+//! (rotation suffixes, syslog headers) without pulling in a date/time crate.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The UTC calendar fields of `time`: `(year, month, day, hour, minute, second)`.
+pub fn civil_from_system_time(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0)).as_secs();
+    let days = (secs / 86400) as i64;
+    let time_of_day = secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    (year, month, day, (time_of_day / 3600) as u32, ((time_of_day % 3600) / 60) as u32, (time_of_day % 60) as u32)
+}
+
+/// Formats `time` as a real RFC3339 UTC timestamp with second precision, e.g.
+/// `2026-07-28T09:30:00Z`.
+pub fn rfc3339(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_from_system_time(time);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)
+}
+
+/// Formats `time` like [`rfc3339`], but with `-` in place of `:` so the result is safe to use in
+/// a filename on all platforms (notably Windows, which rejects `:` in path components).
+pub fn rfc3339_filename_safe(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_from_system_time(time);
+    format!("{:04}-{:02}-{:02}T{:02}-{:02}-{:02}Z", year, month, day, hour, minute, second)
+}
+
+/// Formats `time` in the RFC3164 `Mon dd hh:mm:ss` style (day space-padded, not zero-padded).
+pub fn rfc3164(time: SystemTime) -> String {
+    const MONTHS: [&'static str; 12] =
+        ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (_year, month, day, hour, minute, second) = civil_from_system_time(time);
+    format!("{} {:2} {:02}:{:02}:{:02}", MONTHS[(month - 1) as usize], day, hour, minute, second)
+}
+
+/// Formats `time` against a small `strftime`-like subset: `%Y` `%m` `%d` `%H` `%M` `%S`. Any
+/// other `%`-prefixed sequence, and all other text, passes through unchanged.
+pub fn strftime(time: SystemTime, pattern: &str) -> String {
+    let (year, month, day, hour, minute, second) = civil_from_system_time(time);
+    let mut output = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('Y') => output.push_str(&format!("{:04}", year)),
+            Some('m') => output.push_str(&format!("{:02}", month)),
+            Some('d') => output.push_str(&format!("{:02}", day)),
+            Some('H') => output.push_str(&format!("{:02}", hour)),
+            Some('M') => output.push_str(&format!("{:02}", minute)),
+            Some('S') => output.push_str(&format!("{:02}", second)),
+            Some(other) => {
+                output.push('%');
+                output.push(other);
+            }
+            None => output.push('%'),
+        }
+    }
+
+    output
+}
+
+// Howard Hinnant's civil-from-days algorithm, adapted for a dependency-free calendar conversion.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
@@ -155,7 +155,11 @@
 //! [time]: https://crates.io/crates/time
 //! [time-docs]: https://doc.rust-lang.org/time/time/index.html
 
+#[macro_use]
 extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 
 use std::convert::AsRef;
 use std::path::Path;
@@ -164,10 +168,23 @@ use std::{io, fmt};
 
 pub use builders::{Dispatch, Output};
 pub use errors::InitError;
+pub use rotation::rotating_log_file;
+pub use async_output::async_output;
+pub use reload::Reloadable;
 
 mod builders;
 mod log_impl;
 mod errors;
+mod datetime;
+mod reload;
+pub mod colors;
+pub mod config;
+pub mod rotation;
+pub mod async_output;
+#[cfg(unix)]
+pub mod syslog;
+#[cfg(test)]
+mod test_support;
 
 /// A type alias for a log formatter.
 pub type Formatter = Fn(&mut fmt::Write, &fmt::Arguments, &log::LogRecord) -> fmt::Result + Sync + Send;
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use log;
+
+use builders::Dispatch;
+use {FernLog, Formatter};
+
+/// The base dispatch logger which actually implements `log::Log`. Constructed via
+/// `Dispatch::into_log()`; `Dispatch::set_global()` installs it behind the `Reloadable` proxy,
+/// which forwards every record to it.
+pub struct Logger {
+    pub format: Option<Box<Formatter>>,
+    pub children: Vec<Box<super::FernLog>>,
+    pub level: log::LogLevelFilter,
+    pub filters: Vec<Box<super::Filter>>,
+    pub module_levels: Vec<(String, log::LogLevelFilter)>,
+}
+
+impl Logger {
+    fn level_for(&self, target: &str) -> log::LogLevelFilter {
+        self.module_levels
+            .iter()
+            .filter(|&&(ref module, _)| {
+                target == module.as_str() || target.starts_with(&format!("{}::", module))
+            })
+            .max_by_key(|&&(ref module, _)| module.len())
+            .map(|&(_, level)| level)
+            .unwrap_or(self.level)
+    }
+
+    fn enabled(&self, metadata: &log::LogMetadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target()) && self.filters.iter().all(|f| f(metadata))
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::LogMetadata) -> bool {
+        Logger::enabled(self, metadata)
+    }
+
+    fn log(&self, record: &log::LogRecord) {
+        if !Logger::enabled(self, record.metadata()) {
+            return;
+        }
+
+        self.log_args(record.args(), record);
+    }
+}
+
+impl super::FernLog for Logger {
+    fn log_args(&self, payload: &fmt::Arguments, record: &log::LogRecord) {
+        if !Logger::enabled(self, record.metadata()) {
+            return;
+        }
+
+        match self.format {
+            Some(ref format) => {
+                let mut message = String::new();
+                if format(&mut message, payload, record).is_ok() {
+                    let payload = format_args!("{}", message);
+                    for child in &self.children {
+                        child.log_args(&payload, record);
+                    }
+                }
+            }
+            None => {
+                for child in &self.children {
+                    child.log_args(payload, record);
+                }
+            }
+        }
+    }
+}
+
+impl Dispatch {
+    /// Converts this `Dispatch` into a boxed `Logger` that implements both `log::Log` and
+    /// `FernLog`, ready to be wrapped and installed as the global logger.
+    pub fn into_log(self) -> Box<Logger> {
+        Box::new(Logger {
+            format: self.format,
+            children: self.children.into_iter().map(|output| output.into_fern_log()).collect(),
+            level: self.level,
+            filters: self.filters,
+            module_levels: self.module_levels,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+//! A handle for swapping out the global logger's configuration after `Dispatch::set_global()`,
+//! since `log::set_logger` itself can only be called once per process.
+//!
+//! See [`Reloadable`] for the entry point, returned by `Dispatch::set_global()`.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use log;
+
+use builders::Dispatch;
+use FernLog;
+
+/// The proxy logger installed by `Dispatch::set_global()`. Every `log` call is forwarded to
+/// whatever `Dispatch` is currently installed, so in-flight calls see either the old or the new
+/// configuration, never a torn mix of the two.
+pub struct ReloadableLogger {
+    inner: Arc<RwLock<Box<FernLog>>>,
+}
+
+impl log::Log for ReloadableLogger {
+    fn enabled(&self, _metadata: &log::LogMetadata) -> bool {
+        // Filtering happens inside the installed Dispatch's own level/filters, in `log_args`
+        // below - this proxy can't know the current level ahead of a `reload()`, so it always
+        // lets records through to be filtered there.
+        true
+    }
+
+    fn log(&self, record: &log::LogRecord) {
+        self.log_args(record.args(), record);
+    }
+}
+
+impl FernLog for ReloadableLogger {
+    fn log_args(&self, payload: &fmt::Arguments, record: &log::LogRecord) {
+        self.inner.read().unwrap().log_args(payload, record);
+    }
+}
+
+/// A handle returned by `Dispatch::set_global()` which can swap the global logger's underlying
+/// configuration without restarting the process, e.g. to reload on `SIGHUP`.
+pub struct Reloadable {
+    inner: Arc<RwLock<Box<FernLog>>>,
+}
+
+impl Reloadable {
+    pub(crate) fn install(dispatch: Dispatch) -> (Box<ReloadableLogger>, Reloadable) {
+        let inner = Arc::new(RwLock::new(dispatch.into_shared()));
+        let proxy = Box::new(ReloadableLogger { inner: inner.clone() });
+        (proxy, Reloadable { inner: inner })
+    }
+
+    /// Atomically replaces the currently installed logger configuration with `new_dispatch`.
+    ///
+    /// In-flight `log_args` calls on the old configuration are unaffected; calls starting after
+    /// this returns see `new_dispatch`.
+    pub fn reload(&self, new_dispatch: Dispatch) {
+        *self.inner.write().unwrap() = new_dispatch.into_shared();
+    }
+}
@@ -0,0 +1,349 @@
+//! Rotating file output: `log_file` without the "grows forever" part.
+//!
+//! See [`rotating_log_file`] for the entry point.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log;
+
+use FernLog;
+
+/// A time granularity used by [`Criterion::Age`] and [`Criterion::AgeOrSize`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Age {
+    /// Rotate once per calendar hour (UTC).
+    Hourly,
+    /// Rotate once per calendar day (UTC).
+    Daily,
+}
+
+impl Age {
+    fn bucket(&self, time: SystemTime) -> u64 {
+        let secs = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0)).as_secs();
+        match *self {
+            Age::Hourly => secs / 3600,
+            Age::Daily => secs / 86400,
+        }
+    }
+}
+
+/// Determines when a rotating file output rolls the current file over to a new one.
+#[derive(Copy, Clone, Debug)]
+pub enum Criterion {
+    /// Rotate once the current file reaches this many bytes.
+    Size(u64),
+    /// Rotate once the wall-clock hour/day changes from when the current file was started (for a
+    /// file that already existed when opened, its last-modified time).
+    Age(Age),
+    /// Rotate when either the size or age limit is reached, whichever comes first.
+    AgeOrSize(Age, u64),
+}
+
+impl Criterion {
+    fn should_rotate(&self, bytes_written: u64, created_at: SystemTime, now: SystemTime) -> bool {
+        match *self {
+            Criterion::Size(limit) => bytes_written >= limit,
+            Criterion::Age(age) => age.bucket(created_at) != age.bucket(now),
+            Criterion::AgeOrSize(age, limit) => {
+                bytes_written >= limit || age.bucket(created_at) != age.bucket(now)
+            }
+        }
+    }
+}
+
+/// How rotated files are named relative to the base path.
+#[derive(Copy, Clone, Debug)]
+pub enum Naming {
+    /// Rotated files are suffixed `.1`, `.2`, ... with `.1` being the most recent.
+    Numeric,
+    /// Rotated files are suffixed with the RFC3339 UTC timestamp of the moment they were rotated,
+    /// with `-` in place of `:` so it is a valid filename (e.g. `2026-07-28T09-30-00Z`).
+    Timestamp,
+}
+
+/// A policy for deleting old rotated files after a rotation occurs.
+#[derive(Clone, Debug)]
+pub enum Cleanup {
+    /// Never delete old rotated files.
+    Never,
+    /// Keep only the `n` most recent rotated files, deleting the rest.
+    KeepLogFiles(usize),
+    /// Keep only rotated files whose last-modified time is within this duration of now.
+    KeepForDuration(Duration),
+}
+
+/// A file output which rotates to a new file based on a [`Criterion`], naming rotated files
+/// according to a [`Naming`] scheme and pruning old ones according to a [`Cleanup`] policy.
+///
+/// Construct with [`rotating_log_file`].
+pub struct RotatingFileOutput {
+    state: Mutex<RotationState>,
+}
+
+struct RotationState {
+    base_path: PathBuf,
+    file: File,
+    bytes_written: u64,
+    created_at: SystemTime,
+    criterion: Criterion,
+    naming: Naming,
+    cleanup: Cleanup,
+}
+
+/// Opens a rotating log file at `path`, rotating old contents out according to `criterion`,
+/// naming rotated files according to `naming`, and pruning old rotated files according to
+/// `cleanup`.
+///
+/// ```no_run
+/// # use fern::rotation::{Criterion, Naming, Cleanup, Age};
+/// fern::rotating_log_file(
+///     "output.log",
+///     Criterion::AgeOrSize(Age::Daily, 10 * 1024 * 1024),
+///     Naming::Timestamp,
+///     Cleanup::KeepLogFiles(5),
+/// ).expect("failed to open rotating log file");
+/// ```
+pub fn rotating_log_file<P: AsRef<Path>>(path: P,
+                                          criterion: Criterion,
+                                          naming: Naming,
+                                          cleanup: Cleanup)
+                                          -> Result<RotatingFileOutput, io::Error> {
+    let base_path = path.as_ref().to_path_buf();
+    let file = open_base_file(&base_path)?;
+
+    // If the base path already has content (e.g. the process restarted and re-opened it in
+    // append mode), pick up where it left off rather than starting `Criterion::Size`/`Age`
+    // tracking from zero, or a `Size` limit would never trip until `limit` more bytes land on
+    // top of an already-oversized file.
+    let metadata = file.metadata()?;
+    let bytes_written = metadata.len();
+    let created_at = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+
+    Ok(RotatingFileOutput {
+        state: Mutex::new(RotationState {
+            base_path: base_path,
+            file: file,
+            bytes_written: bytes_written,
+            created_at: created_at,
+            criterion: criterion,
+            naming: naming,
+            cleanup: cleanup,
+        }),
+    })
+}
+
+fn open_base_file(base_path: &Path) -> Result<File, io::Error> {
+    OpenOptions::new().write(true).create(true).append(true).open(base_path)
+}
+
+impl RotationState {
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let rotated_name = match self.naming {
+            Naming::Numeric => {
+                // Shift existing numbered files up by one so `.1` is always the most recent.
+                self.shift_numeric_siblings()?;
+                sibling_with_suffix(&self.base_path, "1")
+            }
+            Naming::Timestamp => {
+                sibling_with_suffix(&self.base_path, &::datetime::rfc3339_filename_safe(SystemTime::now()))
+            }
+        };
+
+        // `rename` works across rotation schemes; only missing source files are tolerated, since
+        // a fresh base path with nothing written yet has nothing to preserve.
+        match fs::rename(&self.base_path, &rotated_name) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        self.file = open_base_file(&self.base_path)?;
+        self.bytes_written = 0;
+        self.created_at = SystemTime::now();
+
+        cleanup_rotated_files(&self.base_path, &self.naming, &self.cleanup)?;
+
+        Ok(())
+    }
+
+    fn shift_numeric_siblings(&self) -> io::Result<()> {
+        let mut index = count_numeric_siblings(&self.base_path);
+        while index >= 1 {
+            let from = sibling_with_suffix(&self.base_path, &index.to_string());
+            let to = sibling_with_suffix(&self.base_path, &(index + 1).to_string());
+            if from.exists() {
+                fs::rename(&from, &to)?;
+            }
+            index -= 1;
+        }
+        Ok(())
+    }
+}
+
+fn count_numeric_siblings(base_path: &Path) -> u64 {
+    let mut count = 0;
+    loop {
+        if sibling_with_suffix(base_path, &(count + 1).to_string()).exists() {
+            count += 1;
+        } else {
+            break;
+        }
+    }
+    count
+}
+
+fn sibling_with_suffix(base_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = base_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn cleanup_rotated_files(base_path: &Path, naming: &Naming, cleanup: &Cleanup) -> io::Result<()> {
+    if let Cleanup::Never = *cleanup {
+        return Ok(());
+    }
+
+    let mut rotated = rotated_sibling_files(base_path)?;
+    // Sort newest-first: numeric suffixes ascend with age (`.1` newest), timestamp suffixes sort
+    // lexicographically the same as chronologically.
+    match *naming {
+        Naming::Numeric => rotated.sort_by_key(|&(ref _path, ref suffix)| {
+            suffix.parse::<u64>().unwrap_or(u64::max_value())
+        }),
+        Naming::Timestamp => rotated.sort_by(|a, b| b.1.cmp(&a.1)),
+    }
+
+    match *cleanup {
+        Cleanup::Never => {}
+        Cleanup::KeepLogFiles(n) => {
+            for &(ref path, _) in rotated.iter().skip(n) {
+                fs::remove_file(path)?;
+            }
+        }
+        Cleanup::KeepForDuration(duration) => {
+            let now = SystemTime::now();
+            for &(ref path, _) in &rotated {
+                let age = fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok());
+                if age.map_or(false, |age| age > duration) {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn rotated_sibling_files(base_path: &Path) -> io::Result<Vec<(PathBuf, String)>> {
+    let dir = base_path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = match base_path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name.to_string(),
+        None => return Ok(Vec::new()),
+    };
+    let prefix = format!("{}.", file_name);
+
+    let mut matches = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if let Some(name) = entry.file_name().to_str() {
+            if name.starts_with(&prefix) {
+                matches.push((entry.path(), name[prefix.len()..].to_string()));
+            }
+        }
+    }
+    Ok(matches)
+}
+
+impl FernLog for RotatingFileOutput {
+    fn log_args(&self, payload: &fmt::Arguments, _record: &log::LogRecord) {
+        let mut state = self.state.lock().unwrap();
+
+        let now = SystemTime::now();
+        if state.criterion.should_rotate(state.bytes_written, state.created_at, now) {
+            if let Err(e) = state.rotate() {
+                // A failed rotation must not take down the application: it's reported on stderr
+                // and the record below is still written to the current file handle.
+                let _ = writeln!(io::stderr(), "fern: failed to rotate log file: {}", e);
+            }
+        }
+
+        let formatted = format!("{}\n", payload);
+        if state.file.write_all(formatted.as_bytes()).is_ok() {
+            state.bytes_written += formatted.len() as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A fresh, empty directory under the system temp dir, removed again on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(label: &str) -> TempDir {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+            let path = env::temp_dir().join(format!("fern_rotation_test_{}_{}_{}", process::id(), label, n));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn cleanup_keep_log_files_prunes_oldest_numeric_siblings() {
+        let dir = TempDir::new("keep_n");
+        let base_path = dir.path().join("output.log");
+
+        for suffix in &["1", "2", "3", "4"] {
+            fs::write(sibling_with_suffix(&base_path, suffix), b"x").unwrap();
+        }
+
+        cleanup_rotated_files(&base_path, &Naming::Numeric, &Cleanup::KeepLogFiles(2)).unwrap();
+
+        assert!(sibling_with_suffix(&base_path, "1").exists());
+        assert!(sibling_with_suffix(&base_path, "2").exists());
+        assert!(!sibling_with_suffix(&base_path, "3").exists());
+        assert!(!sibling_with_suffix(&base_path, "4").exists());
+    }
+
+    #[test]
+    fn cleanup_never_leaves_every_sibling_in_place() {
+        let dir = TempDir::new("never");
+        let base_path = dir.path().join("output.log");
+
+        for suffix in &["1", "2"] {
+            fs::write(sibling_with_suffix(&base_path, suffix), b"x").unwrap();
+        }
+
+        cleanup_rotated_files(&base_path, &Naming::Numeric, &Cleanup::Never).unwrap();
+
+        assert!(sibling_with_suffix(&base_path, "1").exists());
+        assert!(sibling_with_suffix(&base_path, "2").exists());
+    }
+}
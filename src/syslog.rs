@@ -0,0 +1,230 @@
+//! A syslog destination, so `Dispatch::chain` can send records straight to the system log
+//! daemon alongside stdout/stderr/file outputs.
+//!
+//! See [`Syslog`] and its constructors ([`Syslog::unix`], [`Syslog::udp`], [`Syslog::tcp`]) for
+//! the entry points.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+use std::process;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use log;
+
+use async_output::AsyncSink;
+use datetime;
+use FernLog;
+
+/// The standard syslog facility codes (RFC 3164 section 4.1.1).
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Facility {
+    /// `kern` - kernel messages.
+    Kern,
+    /// `user` - user-level messages.
+    User,
+    /// `mail` - mail system.
+    Mail,
+    /// `daemon` - system daemons.
+    Daemon,
+    /// `auth` - security/authorization messages.
+    Auth,
+    /// `local0` through `local7` - locally defined facilities.
+    Local0,
+    /// See [`Facility::Local0`].
+    Local1,
+    /// See [`Facility::Local0`].
+    Local2,
+    /// See [`Facility::Local0`].
+    Local3,
+    /// See [`Facility::Local0`].
+    Local4,
+    /// See [`Facility::Local0`].
+    Local5,
+    /// See [`Facility::Local0`].
+    Local6,
+    /// See [`Facility::Local0`].
+    Local7,
+}
+
+impl Facility {
+    fn code(&self) -> u8 {
+        match *self {
+            Facility::Kern => 0,
+            Facility::User => 1,
+            Facility::Mail => 2,
+            Facility::Daemon => 3,
+            Facility::Auth => 4,
+            Facility::Local0 => 16,
+            Facility::Local1 => 17,
+            Facility::Local2 => 18,
+            Facility::Local3 => 19,
+            Facility::Local4 => 20,
+            Facility::Local5 => 21,
+            Facility::Local6 => 22,
+            Facility::Local7 => 23,
+        }
+    }
+}
+
+/// Which syslog message framing to emit.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// The older, widely-supported `<PRI>Mon dd hh:mm:ss host tag[pid]: msg` format (RFC 3164).
+    Rfc3164,
+    /// The newer, structured `<PRI>1 timestamp host app-name pid - - msg` format (RFC 5424),
+    /// with MSGID and STRUCTURED-DATA both set to the `-` NILVALUE.
+    Rfc5424,
+}
+
+enum Connection {
+    Unix(UnixDatagram),
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        match *self {
+            Connection::Unix(ref socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Connection::Udp(ref socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Connection::Tcp(ref mut stream) => {
+                stream.write_all(message.as_bytes())?;
+                stream.write_all(b"\n")
+            }
+        }
+    }
+}
+
+/// A syslog output, implementing `FernLog` so it can be passed to `Dispatch::chain` like any
+/// other destination.
+///
+/// Since syslog already carries its own severity, timestamp, and host, this is usually paired
+/// with a minimal format that emits just the message body - the header fields below are added
+/// on top of whatever `Dispatch::format` produces.
+pub struct Syslog {
+    connection: Mutex<Connection>,
+    facility: Facility,
+    framing: Framing,
+    app_name: String,
+    pid: u32,
+}
+
+impl Syslog {
+    /// Connects to the local syslog daemon over the Unix datagram socket at `path` (typically
+    /// `/dev/log`).
+    pub fn unix<P: AsRef<Path>>(path: P, facility: Facility) -> io::Result<Syslog> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Syslog::new(Connection::Unix(socket), facility))
+    }
+
+    /// Sends to a remote (or local) syslog daemon over UDP.
+    pub fn udp<A: ToSocketAddrs>(remote: A, facility: Facility) -> io::Result<Syslog> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(remote)?;
+        Ok(Syslog::new(Connection::Udp(socket), facility))
+    }
+
+    /// Sends to a remote (or local) syslog daemon over TCP, with messages newline-delimited.
+    pub fn tcp<A: ToSocketAddrs>(remote: A, facility: Facility) -> io::Result<Syslog> {
+        let stream = TcpStream::connect(remote)?;
+        Ok(Syslog::new(Connection::Tcp(stream), facility))
+    }
+
+    fn new(connection: Connection, facility: Facility) -> Syslog {
+        Syslog {
+            connection: Mutex::new(connection),
+            facility: facility,
+            framing: Framing::Rfc3164,
+            app_name: current_exe_name(),
+            pid: process::id(),
+        }
+    }
+
+    /// Sets the RFC3164/RFC5424 framing to use. Defaults to `Framing::Rfc3164`.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Sets the app/process name reported in the syslog header. Defaults to the current
+    /// executable's name.
+    pub fn app_name<S: Into<String>>(mut self, app_name: S) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    fn severity(level: log::LogLevel) -> u8 {
+        match level {
+            log::LogLevel::Error => 3,
+            log::LogLevel::Warn => 4,
+            log::LogLevel::Info => 6,
+            log::LogLevel::Debug | log::LogLevel::Trace => 7,
+        }
+    }
+
+    fn format_message(&self, payload: &fmt::Arguments, level: log::LogLevel) -> String {
+        let pri = self.facility.code() * 8 + Syslog::severity(level);
+        let now = SystemTime::now();
+
+        match self.framing {
+            Framing::Rfc3164 => {
+                format!("<{}>{} {} {}[{}]: {}",
+                        pri,
+                        datetime::rfc3164(now),
+                        hostname(),
+                        self.app_name,
+                        self.pid,
+                        payload)
+            }
+            Framing::Rfc5424 => {
+                format!("<{}>1 {} {} {} {} - - {}",
+                        pri,
+                        datetime::rfc3339(now),
+                        hostname(),
+                        self.app_name,
+                        self.pid,
+                        payload)
+            }
+        }
+    }
+}
+
+impl FernLog for Syslog {
+    fn log_args(&self, payload: &fmt::Arguments, record: &log::LogRecord) {
+        let message = self.format_message(payload, record.level());
+        let _ = self.connection.lock().unwrap().send(&message);
+    }
+}
+
+impl AsyncSink for Syslog {
+    /// Lets `Syslog` be wrapped by [`::async_output::async_output`] so a slow syslog daemon
+    /// can't stall the logging call site, same as a plain file.
+    fn write_line(&mut self, level: log::LogLevel, line: &str) -> io::Result<()> {
+        let message = self.format_message(&format_args!("{}", line), level);
+        self.connection.get_mut().unwrap().send(&message)
+    }
+}
+
+fn hostname() -> String {
+    use std::fs;
+
+    fs::read_to_string("/etc/hostname")
+        .map(|contents| contents.trim().to_string())
+        .unwrap_or_else(|_| "localhost".to_string())
+}
+
+fn current_exe_name() -> String {
+    use std::env;
+
+    env::current_exe()
+        .ok()
+        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "fern".to_string())
+}
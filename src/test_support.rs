@@ -0,0 +1,50 @@
+//! A harness for obtaining a real `log::LogRecord` in other modules' unit tests.
+//!
+//! `log::LogRecord` has no public constructor outside the `log` crate itself, so the only way
+//! to get one is to round-trip through `log::set_logger` and the logging macros. This installs
+//! one `CapturingLogger` for the whole test binary and hands each call's record to a
+//! caller-supplied callback before it goes out of scope.
+
+use std::sync::Mutex;
+
+use log;
+
+struct CapturingLogger;
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::LogMetadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::LogRecord) {
+        if let Some(mut callback) = CALLBACK.lock().unwrap().take() {
+            callback(record);
+        }
+    }
+}
+
+type Callback = Box<FnMut(&log::LogRecord) + Send>;
+
+static CALLBACK: Mutex<Option<Callback>> = Mutex::new(None);
+// Guards the whole "install callback, trigger log!, read result" sequence, since the captured
+// callback above is a single process-wide slot that concurrently-running tests would otherwise
+// race over.
+static HARNESS: Mutex<()> = Mutex::new(());
+
+/// Logs one record at `level`/`target` with body `message` through the real `log` crate
+/// machinery, and hands the resulting `&log::LogRecord` to `callback`.
+pub(crate) fn with_record<F>(level: log::LogLevel, target: &str, message: &str, mut callback: F)
+    where F: FnMut(&log::LogRecord) + Send + 'static
+{
+    let _guard = HARNESS.lock().unwrap();
+    // Only the first caller in the process actually installs the logger; later calls get
+    // `Err` because one is already set, which is exactly what we want here.
+    let _ = log::set_logger(|max_level| {
+        max_level.set(log::LogLevelFilter::Trace);
+        Box::new(CapturingLogger)
+    });
+
+    *CALLBACK.lock().unwrap() = Some(Box::new(move |record: &log::LogRecord| callback(record)));
+
+    log!(target: target, level, "{}", message);
+}